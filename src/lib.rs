@@ -1,214 +1,1208 @@
-use std::cmp;
-use stdweb::traits::IMouseEvent;
-use yew::{html, Component, ComponentLink, Html, Renderable, ShouldRender};
-
-const HANDLE_RADIUS_PX: i32 = 5;
-const MIN_CARD_SIZE_PX: i32 = 2 * HANDLE_RADIUS_PX + 1;
-
-/// A Card is a transformable image displayed on the Refboard canvas.
-#[derive(PartialEq)]
-pub struct Card {
-    /// The image displayed by this card. This value is directly used in HTML
-    /// `img` tags.
-    image: String,
-
-    /// The absolute position of the top-left corner of this card, represented
-    /// as an (x, y) tuple.
-    position: (i32, i32),
-
-    /// The absolute size of this card, represented as a (width, height) tuple.
-    size: (i32, i32),
-
-    /// The rotation of this card in degrees.
-    rotation: f64,
-
-    /// The Z-index of this card.
-    z: i32,
-}
-
-impl Card {
-    fn rotation_handle_angle(&self) -> f64 {
-        let (width, height) = self.size;
-        (height as f64).atan2(width as f64)
-    }
-}
-
-/// A Model represents the state of the webapp.
-pub struct Model {
-    /// A vector of all cards on the Refboard canvas.
-    cards: Vec<Card>,
-
-    /// The current action bound to mouse movement.
-    drag_state: DragState,
-}
-
-/// A DragState represents an action controlled by holding down the left mouse
-/// button and moving the mouse.
-pub enum DragState {
-    /// Mouse movement should be ignored.
-    None,
-
-    /// The card with the given index should be moved with the cursor.
-    MoveCard(usize),
-
-    /// The card with the given index should be scaled from the bottom-right.
-    MoveScaleHandle(usize),
-
-    /// The card with the given index should be rotated about its center.
-    MoveRotateHandle(usize),
-}
-
-/// A Msg (Message) is a signal sent to the Model requesting a controlled state
-/// change.
-pub enum Msg {
-    CreateCard(String, (i32, i32)),
-    RemoveCard(u32),
-    ResetRotation(usize),
-    StartDraggingScaleHandle(usize),
-    StartDraggingRotateHandle(usize),
-    StartDraggingCard(usize),
-    Drag((i32, i32), (i32, i32)),
-    StopDragging,
-}
-
-impl Component for Model {
-    type Message = Msg;
-    type Properties = ();
-
-    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
-        Model {
-            cards: vec![
-                Card {
-                    image: "".to_string(),
-                    position: (0, 0),
-                    size: (300, 300),
-                    rotation: 0.0,
-                    z: 0,
-                },
-                Card {
-                    image: "".to_string(),
-                    position: (400, 0),
-                    size: (300, 300),
-                    rotation: 0.0,
-                    z: 1,
-                },
-            ],
-            drag_state: DragState::None,
-        }
-    }
-
-    fn update(&mut self, msg: Self::Message) -> ShouldRender {
-        match msg {
-            Msg::StartDraggingCard(idx) => {
-                self.drag_state = DragState::MoveCard(idx);
-                let selected_card_z = self.cards[idx].z;
-
-                for mut card in &mut self.cards {
-                    if card.z >= selected_card_z {
-                        card.z -= 1;
-                    }
-                }
-
-                self.cards[idx].z = (self.cards.len() - 1) as i32;
-
-                true
-            }
-            Msg::StartDraggingScaleHandle(idx) => {
-                self.drag_state = DragState::MoveScaleHandle(idx);
-                true
-            }
-            Msg::StartDraggingRotateHandle(idx) => {
-                self.drag_state = DragState::MoveRotateHandle(idx);
-                true
-            }
-            Msg::ResetRotation(idx) => {
-                self.cards[idx].rotation = 0.0;
-                true
-            }
-            Msg::Drag(delta, pos) => match self.drag_state {
-                DragState::MoveCard(idx) => {
-                    let card = &mut self.cards[idx];
-
-                    card.position.0 += delta.0;
-                    card.position.1 += delta.1;
-
-                    true
-                }
-                DragState::MoveScaleHandle(idx) => {
-                    let card = &mut self.cards[idx];
-
-                    card.size.0 = cmp::max(MIN_CARD_SIZE_PX, card.size.0 + delta.0);
-                    card.size.1 = cmp::max(MIN_CARD_SIZE_PX, card.size.1 + delta.1);
-
-                    true
-                }
-                DragState::MoveRotateHandle(idx) => {
-                    let card = &mut self.cards[idx];
-                    let (cursor_x, cursor_y) = pos;
-                    let (x, y) = card.position;
-                    let (width, height) = card.size;
-
-                    let atan_x: f64 = (cursor_x - (x + (width / 2))).into();
-                    let atan_y: f64 = (cursor_y - (y + (height / 2))).into();
-
-                    card.rotation = atan_y.atan2(atan_x) + card.rotation_handle_angle();
-
-                    true
-                }
-                DragState::None => false,
-            },
-            Msg::StopDragging => {
-                self.drag_state = DragState::None;
-                true
-            }
-            _ => true,
-        }
-    }
-}
-
-impl Renderable<Model> for Model {
-    fn view(&self) -> Html<Self> {
-        html! {
-            <div class="refboard",
-                    onmousemove=|e| Msg::Drag((e.movement_x(), e.movement_y()), (e.client_x(), e.client_y())),
-                    onmouseup=|_| Msg::StopDragging,>
-                { for self.cards.iter().map(|c| self.view_card(c)) }
-            </div>
-        }
-    }
-}
-
-impl Model {
-    fn view_card(&self, card: &Card) -> Html<Model> {
-        let card_idx = self.cards.iter().position(|c| c == card);
-
-        match card_idx {
-            Some(idx) => html! {
-                <div class="unselectable card",
-                        style=format!("left: {}px; top: {}px; width: {}px; height: {}px; transform: rotate({}rad); z-index: {};", card.position.0, card.position.1, card.size.0, card.size.1, card.rotation, card.z),>
-
-                    // Transformation handles
-
-                    <div class="scaling-handle",
-                        style=format!("right: -{}px; bottom: -{}px;", HANDLE_RADIUS_PX, HANDLE_RADIUS_PX),
-                        onmousedown=|_| Msg::StartDraggingScaleHandle(idx),
-                        ondragstart=|_| Msg::StartDraggingScaleHandle(idx),></div>
-
-                    <div class="rotation-handle",
-                        style=format!("right: -{}px; top: -{}px;", HANDLE_RADIUS_PX, HANDLE_RADIUS_PX),
-                        onmousedown=|_| Msg::StartDraggingRotateHandle(idx),
-                        ondragstart=|_| Msg::StartDraggingRotateHandle(idx),
-                        oncontextmenu=|_| Msg::ResetRotation(idx),></div>
-
-                    // Actual image body
-
-                    <div class="image",
-                        onmousedown=|_| Msg::StartDraggingCard(idx),
-                        ondragstart=|_| Msg::StartDraggingCard(idx),
-                        style=format!("width: {}px; height: {}px", card.size.0, card.size.1),></div>
-                </div>
-            },
-            None => html! {},
-        }
-    }
-}
+#[macro_use]
+extern crate stdweb;
+
+use std::cmp;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use stdweb::traits::{IDragEvent, IEvent, IMouseEvent};
+use stdweb::web::event::{LoadEndEvent, MouseButton, ResourceLoadEvent};
+use stdweb::web::html_element::ImageElement;
+use stdweb::web::{window, File, FileReader, FileReaderResult, IEventTarget};
+use yew::services::{IntervalService, IntervalTask};
+use yew::{html, Component, ComponentLink, Html, Renderable, ShouldRender};
+
+const HANDLE_RADIUS_PX: i32 = 5;
+const MIN_CARD_SIZE_PX: i32 = 2 * HANDLE_RADIUS_PX + 1;
+
+/// The size assigned to a freshly-imported card before its image has
+/// finished loading and reported its natural dimensions.
+const DEFAULT_CARD_SIZE_PX: (i32, i32) = (300, 300);
+
+const MIN_ZOOM: f64 = 0.1;
+const MAX_ZOOM: f64 = 8.0;
+
+/// The exponential zoom rate per unit of wheel `deltaY`: the zoom factor is
+/// multiplied by `exp(-deltaY * ZOOM_SENSITIVITY)`.
+const ZOOM_SENSITIVITY: f64 = 0.001;
+
+/// The `localStorage` key the board is auto-persisted under.
+const LOCAL_STORAGE_KEY: &str = "refboard.cards";
+
+/// How close, in board space, a card edge needs to be to a candidate
+/// alignment line before it snaps to it.
+const SNAP_THRESHOLD_PX: i32 = 6;
+
+/// How far a rendered guide line extends to either side of the point it
+/// passes through, since the board itself has no fixed bounds.
+const GUIDE_LINE_REACH_PX: i32 = 10_000;
+
+/// How often an active animation is advanced and the board repainted.
+const ANIMATION_TICK_MS: u64 = 16;
+
+/// How long a rotation eases back to zero after `Msg::ResetRotation`.
+const ROTATION_RESET_DURATION_MS: f64 = 200.0;
+
+/// How long a card eases into its new layer position after a z-index
+/// shuffle, so reordering reads as a settle rather than a snap.
+const Z_SETTLE_DURATION_MS: f64 = 150.0;
+
+/// A Card is a transformable image displayed on the Refboard canvas.
+#[derive(PartialEq, Serialize, Deserialize)]
+pub struct Card {
+    /// The image displayed by this card. This value is directly used as the
+    /// CSS `background-image` URL of the card's `.image` div.
+    image: String,
+
+    /// The absolute position of the top-left corner of this card, represented
+    /// as an (x, y) tuple.
+    position: (i32, i32),
+
+    /// The absolute size of this card, represented as a (width, height) tuple.
+    size: (i32, i32),
+
+    /// The rotation of this card in radians.
+    rotation: f64,
+
+    /// The Z-index of this card.
+    z: i32,
+}
+
+impl Card {
+    fn rotation_handle_angle(&self) -> f64 {
+        let (width, height) = self.size;
+        (height as f64).atan2(width as f64)
+    }
+}
+
+/// One of the four corners of a card's scaling handles.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// All four corners, in the order their handles are rendered.
+const CORNERS: [Corner; 4] = [
+    Corner::TopLeft,
+    Corner::TopRight,
+    Corner::BottomLeft,
+    Corner::BottomRight,
+];
+
+impl Corner {
+    /// The diagonally opposite corner, which stays fixed while this one is
+    /// dragged.
+    fn opposite(self) -> Corner {
+        match self {
+            Corner::TopLeft => Corner::BottomRight,
+            Corner::TopRight => Corner::BottomLeft,
+            Corner::BottomLeft => Corner::TopRight,
+            Corner::BottomRight => Corner::TopLeft,
+        }
+    }
+
+    /// The sign to apply to a card-local delta along each axis so that
+    /// dragging this corner outward grows the card.
+    fn sign(self) -> (f64, f64) {
+        match self {
+            Corner::TopLeft => (-1.0, -1.0),
+            Corner::TopRight => (1.0, -1.0),
+            Corner::BottomLeft => (-1.0, 1.0),
+            Corner::BottomRight => (1.0, 1.0),
+        }
+    }
+
+    /// The CSS properties that pin a handle to this corner.
+    fn css_position(self) -> (&'static str, &'static str) {
+        match self {
+            Corner::TopLeft => ("left", "top"),
+            Corner::TopRight => ("right", "top"),
+            Corner::BottomLeft => ("left", "bottom"),
+            Corner::BottomRight => ("right", "bottom"),
+        }
+    }
+}
+
+/// A matched alignment guide line, shown while a card being dragged snaps
+/// to it.
+enum Guide {
+    Vertical(i32),
+    Horizontal(i32),
+}
+
+/// The modifier keys held during a drag, which change how it's interpreted:
+/// Shift locks aspect ratio while scaling, Alt disables snapping while
+/// moving.
+#[derive(Clone, Copy)]
+pub struct DragModifiers {
+    shift: bool,
+    alt: bool,
+}
+
+/// A property of a `Card` that can be eased toward a target value over
+/// time, rather than snapped to it instantly.
+#[derive(Clone, Copy, PartialEq)]
+enum AnimatedProperty {
+    Rotation,
+    Z,
+}
+
+/// An in-progress ease of a single card property from `from` to `to`,
+/// running over `duration_ms` starting at `started_at`.
+struct Animation {
+    card_idx: usize,
+    property: AnimatedProperty,
+    from: f64,
+    to: f64,
+    started_at: f64,
+    duration_ms: f64,
+}
+
+impl Animation {
+    /// Milliseconds elapsed since the animation started, as of `now`.
+    fn elapsed(&self, now: f64) -> f64 {
+        now - self.started_at
+    }
+
+    /// Whether the animation has reached its target value as of `now`.
+    fn finished(&self, now: f64) -> bool {
+        self.elapsed(now) >= self.duration_ms
+    }
+
+    /// The eased value at `now`, using an ease-out curve.
+    fn value(&self, now: f64) -> f64 {
+        let t = (self.elapsed(now) / self.duration_ms).max(0.0).min(1.0);
+        let eased = 1.0 - (1.0 - t) * (1.0 - t);
+
+        self.from + (self.to - self.from) * eased
+    }
+}
+
+/// Finds the candidate in `candidates` closest to any of `lines`, within
+/// `SNAP_THRESHOLD_PX`. Returns the signed correction to apply to `lines`
+/// and the matched candidate, if any.
+fn best_snap(lines: [i32; 3], candidates: &[i32]) -> Option<(i32, i32)> {
+    let mut best: Option<(i32, i32, i32)> = None;
+
+    for &line in lines.iter() {
+        for &candidate in candidates {
+            let correction = candidate - line;
+
+            if correction.abs() <= SNAP_THRESHOLD_PX
+                && best.map_or(true, |(best_abs, _, _)| correction.abs() < best_abs)
+            {
+                best = Some((correction.abs(), correction, candidate));
+            }
+        }
+    }
+
+    best.map(|(_, correction, candidate)| (correction, candidate))
+}
+
+/// A Model represents the state of the webapp.
+pub struct Model {
+    /// A vector of all cards on the Refboard canvas.
+    cards: Vec<Card>,
+
+    /// The current action bound to mouse movement.
+    drag_state: DragState,
+
+    /// The screen-space offset of the board's origin, in pixels. Applied to
+    /// the `.refboard` div via a CSS `translate`.
+    pan: (f64, f64),
+
+    /// The scale factor applied to the board, via a CSS `scale` transform.
+    zoom: f64,
+
+    /// The indices of the cards currently selected, in board space. More
+    /// than one index means the selection can be moved, scaled, and rotated
+    /// together as a group.
+    selection: Vec<usize>,
+
+    /// The board-space point the cursor is currently at while a
+    /// `DragState::RubberBand` is active, used to render the selection box.
+    rubber_band_current: (i32, i32),
+
+    /// The screen-space point the cursor was last seen at, tracked on every
+    /// `onmousemove` so paste events (which carry no position of their own)
+    /// can still place the new card under the cursor.
+    last_mouse_position: (i32, i32),
+
+    /// The alignment guides currently matched by a snapping card drag.
+    guides: Vec<Guide>,
+
+    /// The eased transitions currently in flight, advanced once per
+    /// `Msg::Tick`.
+    animations: Vec<Animation>,
+
+    /// The task driving `Msg::Tick` while `animations` is non-empty.
+    /// Dropped once every animation has finished, so the board is idle
+    /// between transitions.
+    ticker: Option<IntervalTask>,
+
+    /// A handle back into the component, used to dispatch messages from
+    /// callbacks that live outside of `view` (e.g. `FileReader` and
+    /// `ImageElement` load events).
+    link: ComponentLink<Model>,
+}
+
+/// A DragState represents an action controlled by holding down the left mouse
+/// button and moving the mouse.
+pub enum DragState {
+    /// Mouse movement should be ignored.
+    None,
+
+    /// The selected cards should be moved with the cursor. The card with the
+    /// given index is the one grabbed, and its edges are the ones snapped.
+    MoveCard(usize),
+
+    /// The card with the given index should be scaled from the given
+    /// corner, keeping the diagonally opposite corner fixed in place.
+    MoveScaleHandle(usize, Corner),
+
+    /// The card with the given index should be rotated about its center.
+    MoveRotateHandle(usize),
+
+    /// The board itself should be panned with the cursor.
+    PanCanvas,
+
+    /// A selection rectangle is being drawn from `origin` to the cursor.
+    RubberBand { origin: (i32, i32) },
+
+    /// The selected cards should be scaled together, keeping `anchor` (the
+    /// opposite corner of the group's bounding box) fixed in place.
+    ScaleGroup { anchor: (i32, i32) },
+
+    /// The selected cards should be rotated together about `center`, the
+    /// group's bounding box center at the moment the drag started.
+    RotateGroup { center: (i32, i32), last_angle: f64 },
+}
+
+/// A Msg (Message) is a signal sent to the Model requesting a controlled state
+/// change.
+pub enum Msg {
+    CreateCard(String, (i32, i32)),
+    RemoveCard(u32),
+    ResetRotation(usize),
+    StartDraggingScaleHandle(usize, Corner),
+    StartDraggingRotateHandle(usize),
+    StartDraggingCard(usize),
+    StartPanningCanvas,
+    /// A selection rectangle should start being drawn from the given
+    /// screen-space point.
+    StartRubberBand((i32, i32)),
+    /// The group scale handle was grabbed; the current selection should
+    /// scale together.
+    StartDraggingGroupScaleHandle,
+    /// The group rotate handle was grabbed at the given screen-space point;
+    /// the current selection should rotate together.
+    StartDraggingGroupRotateHandle((i32, i32)),
+    /// A drag moved by the given delta to the given screen-space position,
+    /// with the given modifier keys held.
+    Drag((i32, i32), (i32, i32), DragModifiers),
+    StopDragging,
+    /// The wheel was scrolled over the board by the given delta, with the
+    /// cursor at the given screen-space position; zoom toward that cursor.
+    Zoom(f64, (i32, i32)),
+    /// One or more files were dropped or pasted onto the board; each should
+    /// be read and turned into a card centered on the given position.
+    ImportFiles(Vec<File>, (i32, i32)),
+    /// The image belonging to the card at the given index finished loading
+    /// and reported its natural size.
+    SetCardSize(usize, (i32, i32)),
+    /// Serialize the board and offer it as a `.json` download.
+    SaveBoard,
+    /// Replace the board with the cards encoded in the given JSON string.
+    LoadBoard(String),
+    /// A board `.json` file was chosen for import; it should be read and
+    /// turned into a `LoadBoard` once its contents are available.
+    ImportBoardFiles(Vec<File>),
+    /// A tick of the animation loop; active animations should be checked
+    /// for completion and the board repainted.
+    Tick,
+    /// Nothing to do; used by handlers that only need to call
+    /// `prevent_default` on their event.
+    Ignore,
+}
+
+impl Model {
+    /// Converts a point in screen space (e.g. from `client_x`/`client_y`)
+    /// into board space, accounting for the current pan and zoom.
+    fn screen_to_board(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        (
+            ((x as f64 - self.pan.0) / self.zoom) as i32,
+            ((y as f64 - self.pan.1) / self.zoom) as i32,
+        )
+    }
+
+    /// Converts a movement delta in screen space (e.g. from `movement_x`/
+    /// `movement_y`) into board space, accounting for the current zoom.
+    fn delta_to_board(&self, (dx, dy): (i32, i32)) -> (i32, i32) {
+        ((dx as f64 / self.zoom) as i32, (dy as f64 / self.zoom) as i32)
+    }
+
+    /// Returns the (min, max) corners of the bounding box around every
+    /// selected card, or `None` if nothing is selected.
+    fn selection_bounds(&self) -> Option<((i32, i32), (i32, i32))> {
+        self.selection.iter().fold(None, |bounds, &idx| {
+            let card = &self.cards[idx];
+            let (x, y) = card.position;
+            let (width, height) = card.size;
+            let (card_min, card_max) = ((x, y), (x + width, y + height));
+
+            Some(match bounds {
+                None => (card_min, card_max),
+                Some((min, max)) => (
+                    (cmp::min(min.0, card_min.0), cmp::min(min.1, card_min.1)),
+                    (cmp::max(max.0, card_max.0), cmp::max(max.1, card_max.1)),
+                ),
+            })
+        })
+    }
+
+    /// Loads the board previously auto-persisted by `persist_to_local_storage`,
+    /// if any.
+    fn load_from_local_storage() -> Option<Vec<Card>> {
+        window()
+            .local_storage()
+            .get(LOCAL_STORAGE_KEY)
+            .and_then(|json| serde_json::from_str(&json).ok())
+    }
+
+    /// Serializes the board and writes it to `localStorage`, so it survives
+    /// a page reload.
+    fn persist_to_local_storage(&self) {
+        if let Ok(json) = serde_json::to_string(&self.cards) {
+            let _ = window().local_storage().insert(LOCAL_STORAGE_KEY, &json);
+        }
+    }
+
+    /// The rotation the card at `idx` should currently be rendered with,
+    /// easing from an in-progress `Animation` if one is active.
+    fn display_rotation(&self, idx: usize) -> f64 {
+        let now = now_ms();
+
+        self.animations
+            .iter()
+            .find(|a| a.card_idx == idx && a.property == AnimatedProperty::Rotation)
+            .map(|a| a.value(now))
+            .unwrap_or(self.cards[idx].rotation)
+    }
+
+    /// The z-index the card at `idx` should currently be rendered with,
+    /// easing from an in-progress `Animation` if one is active.
+    fn display_z(&self, idx: usize) -> i32 {
+        let now = now_ms();
+
+        self.animations
+            .iter()
+            .find(|a| a.card_idx == idx && a.property == AnimatedProperty::Z)
+            .map(|a| a.value(now).round() as i32)
+            .unwrap_or(self.cards[idx].z)
+    }
+
+    /// Starts the `Msg::Tick` loop if it isn't already running, so that
+    /// any newly-added animations get advanced and repainted.
+    fn ensure_animation_loop(&mut self) {
+        if self.ticker.is_none() {
+            let callback = self.link.send_back(|_| Msg::Tick);
+            let task = IntervalService::new()
+                .spawn(Duration::from_millis(ANIMATION_TICK_MS), callback);
+
+            self.ticker = Some(task);
+        }
+    }
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let cards = Model::load_from_local_storage().unwrap_or_else(|| {
+            vec![
+                Card {
+                    image: "".to_string(),
+                    position: (0, 0),
+                    size: (300, 300),
+                    rotation: 0.0,
+                    z: 0,
+                },
+                Card {
+                    image: "".to_string(),
+                    position: (400, 0),
+                    size: (300, 300),
+                    rotation: 0.0,
+                    z: 1,
+                },
+            ]
+        });
+
+        Model {
+            cards,
+            drag_state: DragState::None,
+            pan: (0.0, 0.0),
+            zoom: 1.0,
+            selection: Vec::new(),
+            rubber_band_current: (0, 0),
+            last_mouse_position: (0, 0),
+            guides: Vec::new(),
+            animations: Vec::new(),
+            ticker: None,
+            link,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        let mut cards_changed = false;
+
+        let should_render = match msg {
+            Msg::CreateCard(image, screen_position) => {
+                let (x, y) = self.screen_to_board(screen_position);
+                let (width, height) = DEFAULT_CARD_SIZE_PX;
+                let z = self.cards.len() as i32;
+
+                self.cards.push(Card {
+                    image: image.clone(),
+                    position: (x - width / 2, y - height / 2),
+                    size: DEFAULT_CARD_SIZE_PX,
+                    rotation: 0.0,
+                    z,
+                });
+
+                probe_image_size(self.link.clone(), self.cards.len() - 1, image);
+
+                cards_changed = true;
+                true
+            }
+            Msg::SetCardSize(idx, size) => {
+                let card = &mut self.cards[idx];
+                let (width, height) = size;
+
+                if width > 0 && height > 0 {
+                    card.position.0 -= (width - card.size.0) / 2;
+                    card.position.1 -= (height - card.size.1) / 2;
+                    card.size = size;
+                    cards_changed = true;
+                }
+
+                true
+            }
+            Msg::ImportFiles(files, position) => {
+                for file in files {
+                    import_file(self.link.clone(), file, position);
+                }
+
+                false
+            }
+            Msg::StartDraggingCard(idx) => {
+                if !self.selection.contains(&idx) {
+                    self.selection = vec![idx];
+                }
+
+                self.drag_state = DragState::MoveCard(idx);
+                let selected_card_z = self.cards[idx].z;
+                let top_z = (self.cards.len() - 1) as i32;
+                let now = now_ms();
+
+                for (i, mut card) in self.cards.iter_mut().enumerate() {
+                    let to = if i == idx {
+                        top_z
+                    } else if card.z >= selected_card_z {
+                        card.z - 1
+                    } else {
+                        card.z
+                    };
+
+                    if to == card.z {
+                        continue;
+                    }
+
+                    let from = self
+                        .animations
+                        .iter()
+                        .find(|a| a.card_idx == i && a.property == AnimatedProperty::Z)
+                        .map(|a| a.value(now))
+                        .unwrap_or(card.z as f64);
+
+                    self.animations
+                        .retain(|a| !(a.card_idx == i && a.property == AnimatedProperty::Z));
+                    self.animations.push(Animation {
+                        card_idx: i,
+                        property: AnimatedProperty::Z,
+                        from,
+                        to: to as f64,
+                        started_at: now,
+                        duration_ms: Z_SETTLE_DURATION_MS,
+                    });
+
+                    card.z = to;
+                    cards_changed = true;
+                }
+
+                self.ensure_animation_loop();
+
+                true
+            }
+            Msg::StartDraggingScaleHandle(idx, corner) => {
+                self.drag_state = DragState::MoveScaleHandle(idx, corner);
+                true
+            }
+            Msg::StartDraggingRotateHandle(idx) => {
+                self.drag_state = DragState::MoveRotateHandle(idx);
+                true
+            }
+            Msg::StartPanningCanvas => {
+                self.drag_state = DragState::PanCanvas;
+                true
+            }
+            Msg::StartRubberBand(screen_origin) => {
+                let origin = self.screen_to_board(screen_origin);
+
+                self.selection.clear();
+                self.rubber_band_current = origin;
+                self.drag_state = DragState::RubberBand { origin };
+
+                true
+            }
+            Msg::StartDraggingGroupScaleHandle => {
+                if let Some((anchor, _)) = self.selection_bounds() {
+                    self.drag_state = DragState::ScaleGroup { anchor };
+                }
+
+                true
+            }
+            Msg::StartDraggingGroupRotateHandle(screen_pos) => {
+                if let Some((min, max)) = self.selection_bounds() {
+                    let center = ((min.0 + max.0) / 2, (min.1 + max.1) / 2);
+                    let (x, y) = self.screen_to_board(screen_pos);
+                    let last_angle = ((y - center.1) as f64).atan2((x - center.0) as f64);
+
+                    self.drag_state = DragState::RotateGroup { center, last_angle };
+                }
+
+                true
+            }
+            Msg::Zoom(wheel_delta, cursor) => {
+                let (anchor_x, anchor_y) = self.screen_to_board(cursor);
+                let factor = (-wheel_delta * ZOOM_SENSITIVITY).exp();
+
+                self.zoom = (self.zoom * factor).max(MIN_ZOOM).min(MAX_ZOOM);
+                self.pan.0 = cursor.0 as f64 - anchor_x as f64 * self.zoom;
+                self.pan.1 = cursor.1 as f64 - anchor_y as f64 * self.zoom;
+
+                true
+            }
+            Msg::ResetRotation(idx) => {
+                let from = self.display_rotation(idx);
+
+                self.animations
+                    .retain(|a| !(a.card_idx == idx && a.property == AnimatedProperty::Rotation));
+                self.cards[idx].rotation = 0.0;
+                cards_changed = from != 0.0;
+
+                if from != 0.0 {
+                    self.animations.push(Animation {
+                        card_idx: idx,
+                        property: AnimatedProperty::Rotation,
+                        from,
+                        to: 0.0,
+                        started_at: now_ms(),
+                        duration_ms: ROTATION_RESET_DURATION_MS,
+                    });
+                    self.ensure_animation_loop();
+                }
+
+                true
+            }
+            Msg::Tick => {
+                let now = now_ms();
+
+                self.animations.retain(|a| !a.finished(now));
+
+                if self.animations.is_empty() {
+                    self.ticker = None;
+                }
+
+                true
+            }
+            Msg::Drag(delta, pos, modifiers) => {
+                self.last_mouse_position = pos;
+
+                match self.drag_state {
+                    DragState::MoveCard(anchor_idx) => {
+                        let delta = self.delta_to_board(delta);
+                        cards_changed = true;
+
+                        for &idx in &self.selection {
+                            let card = &mut self.cards[idx];
+
+                            card.position.0 += delta.0;
+                            card.position.1 += delta.1;
+                        }
+
+                        self.guides.clear();
+
+                        if !modifiers.alt {
+                            let (anchor_position, anchor_size) = {
+                                let anchor = &self.cards[anchor_idx];
+                                (anchor.position, anchor.size)
+                            };
+                            let anchor_xs = [
+                                anchor_position.0,
+                                anchor_position.0 + anchor_size.0 / 2,
+                                anchor_position.0 + anchor_size.0,
+                            ];
+                            let anchor_ys = [
+                                anchor_position.1,
+                                anchor_position.1 + anchor_size.1 / 2,
+                                anchor_position.1 + anchor_size.1,
+                            ];
+
+                            // The board origin stands in for "canvas center",
+                            // since the board itself has no fixed extent.
+                            let mut candidate_xs = vec![0];
+                            let mut candidate_ys = vec![0];
+
+                            for (i, other) in self.cards.iter().enumerate() {
+                                if self.selection.contains(&i) {
+                                    continue;
+                                }
+
+                                let (x, y) = other.position;
+                                let (w, h) = other.size;
+
+                                candidate_xs.extend_from_slice(&[x, x + w / 2, x + w]);
+                                candidate_ys.extend_from_slice(&[y, y + h / 2, y + h]);
+                            }
+
+                            let x_snap = best_snap(anchor_xs, &candidate_xs);
+                            let y_snap = best_snap(anchor_ys, &candidate_ys);
+                            let correction = (
+                                x_snap.map_or(0, |(correction, _)| correction),
+                                y_snap.map_or(0, |(correction, _)| correction),
+                            );
+
+                            for &idx in &self.selection {
+                                let card = &mut self.cards[idx];
+
+                                card.position.0 += correction.0;
+                                card.position.1 += correction.1;
+                            }
+
+                            if let Some((_, matched)) = x_snap {
+                                self.guides.push(Guide::Vertical(matched));
+                            }
+
+                            if let Some((_, matched)) = y_snap {
+                                self.guides.push(Guide::Horizontal(matched));
+                            }
+                        }
+
+                        true
+                    }
+                    DragState::MoveScaleHandle(idx, corner) => {
+                        let delta = self.delta_to_board(delta);
+                        cards_changed = true;
+                        let card = &mut self.cards[idx];
+                        let (sin, cos) = card.rotation.sin_cos();
+
+                        // Rotate the board-space delta into the card's local,
+                        // unrotated frame.
+                        let local_dx = delta.0 as f64 * cos + delta.1 as f64 * sin;
+                        let local_dy = -(delta.0 as f64) * sin + delta.1 as f64 * cos;
+
+                        let (sign_x, sign_y) = corner.sign();
+                        let (old_width, old_height) = card.size;
+                        let mut new_width = old_width + (sign_x * local_dx) as i32;
+                        let mut new_height = old_height + (sign_y * local_dy) as i32;
+
+                        if modifiers.shift {
+                            let width_ratio = new_width as f64 / old_width as f64;
+                            let height_ratio = new_height as f64 / old_height as f64;
+                            let ratio = if (width_ratio - 1.0).abs() > (height_ratio - 1.0).abs() {
+                                width_ratio
+                            } else {
+                                height_ratio
+                            };
+
+                            new_width = (old_width as f64 * ratio) as i32;
+                            new_height = (old_height as f64 * ratio) as i32;
+                        }
+
+                        new_width = cmp::max(MIN_CARD_SIZE_PX, new_width);
+                        new_height = cmp::max(MIN_CARD_SIZE_PX, new_height);
+
+                        // Keep the diagonally opposite corner fixed in world
+                        // space by re-deriving the card's center from it.
+                        let old_center = (
+                            card.position.0 as f64 + old_width as f64 / 2.0,
+                            card.position.1 as f64 + old_height as f64 / 2.0,
+                        );
+                        let (opp_sign_x, opp_sign_y) = corner.opposite().sign();
+                        let anchor_local = (
+                            opp_sign_x * old_width as f64 / 2.0,
+                            opp_sign_y * old_height as f64 / 2.0,
+                        );
+                        let anchor_world = (
+                            old_center.0 + anchor_local.0 * cos - anchor_local.1 * sin,
+                            old_center.1 + anchor_local.0 * sin + anchor_local.1 * cos,
+                        );
+
+                        let anchor_local_new = (
+                            opp_sign_x * new_width as f64 / 2.0,
+                            opp_sign_y * new_height as f64 / 2.0,
+                        );
+                        let new_center = (
+                            anchor_world.0 - (anchor_local_new.0 * cos - anchor_local_new.1 * sin),
+                            anchor_world.1 - (anchor_local_new.0 * sin + anchor_local_new.1 * cos),
+                        );
+
+                        card.size = (new_width, new_height);
+                        card.position = (
+                            (new_center.0 - new_width as f64 / 2.0) as i32,
+                            (new_center.1 - new_height as f64 / 2.0) as i32,
+                        );
+
+                        true
+                    }
+                    DragState::MoveRotateHandle(idx) => {
+                        let (cursor_x, cursor_y) = self.screen_to_board(pos);
+                        cards_changed = true;
+                        let card = &mut self.cards[idx];
+                        let (x, y) = card.position;
+                        let (width, height) = card.size;
+
+                        let atan_x: f64 = (cursor_x - (x + (width / 2))).into();
+                        let atan_y: f64 = (cursor_y - (y + (height / 2))).into();
+
+                        card.rotation = atan_y.atan2(atan_x) + card.rotation_handle_angle();
+
+                        true
+                    }
+                    DragState::PanCanvas => {
+                        self.pan.0 += delta.0 as f64;
+                        self.pan.1 += delta.1 as f64;
+
+                        true
+                    }
+                    DragState::RubberBand { .. } => {
+                        self.rubber_band_current = self.screen_to_board(pos);
+                        true
+                    }
+                    DragState::ScaleGroup { anchor } => {
+                        let delta = self.delta_to_board(delta);
+
+                        if let Some((min, max)) = self.selection_bounds() {
+                            let old_size = (
+                                cmp::max(1, max.0 - min.0),
+                                cmp::max(1, max.1 - min.1),
+                            );
+                            let new_size = (
+                                cmp::max(MIN_CARD_SIZE_PX, old_size.0 + delta.0),
+                                cmp::max(MIN_CARD_SIZE_PX, old_size.1 + delta.1),
+                            );
+                            let scale = (
+                                new_size.0 as f64 / old_size.0 as f64,
+                                new_size.1 as f64 / old_size.1 as f64,
+                            );
+
+                            for &idx in &self.selection {
+                                let card = &mut self.cards[idx];
+                                let relative = (card.position.0 - anchor.0, card.position.1 - anchor.1);
+
+                                card.position = (
+                                    anchor.0 + (relative.0 as f64 * scale.0) as i32,
+                                    anchor.1 + (relative.1 as f64 * scale.1) as i32,
+                                );
+                                card.size = (
+                                    cmp::max(MIN_CARD_SIZE_PX, (card.size.0 as f64 * scale.0) as i32),
+                                    cmp::max(MIN_CARD_SIZE_PX, (card.size.1 as f64 * scale.1) as i32),
+                                );
+                            }
+
+                            cards_changed = true;
+                        }
+
+                        true
+                    }
+                    DragState::RotateGroup { center, last_angle } => {
+                        let (cursor_x, cursor_y) = self.screen_to_board(pos);
+                        let angle = ((cursor_y - center.1) as f64).atan2((cursor_x - center.0) as f64);
+                        let delta_angle = angle - last_angle;
+                        let (sin, cos) = delta_angle.sin_cos();
+
+                        for &idx in &self.selection {
+                            let card = &mut self.cards[idx];
+                            let (width, height) = card.size;
+                            let card_center = (
+                                card.position.0 as f64 + width as f64 / 2.0,
+                                card.position.1 as f64 + height as f64 / 2.0,
+                            );
+                            let relative = (card_center.0 - center.0 as f64, card_center.1 - center.1 as f64);
+                            let rotated = (
+                                relative.0 * cos - relative.1 * sin,
+                                relative.0 * sin + relative.1 * cos,
+                            );
+
+                            card.position = (
+                                (center.0 as f64 + rotated.0 - width as f64 / 2.0) as i32,
+                                (center.1 as f64 + rotated.1 - height as f64 / 2.0) as i32,
+                            );
+                            card.rotation += delta_angle;
+                        }
+
+                        cards_changed = true;
+                        self.drag_state = DragState::RotateGroup { center, last_angle: angle };
+
+                        true
+                    }
+                    DragState::None => false,
+                }
+            }
+            Msg::StopDragging => {
+                if let DragState::RubberBand { origin } = self.drag_state {
+                    let min = (
+                        cmp::min(origin.0, self.rubber_band_current.0),
+                        cmp::min(origin.1, self.rubber_band_current.1),
+                    );
+                    let max = (
+                        cmp::max(origin.0, self.rubber_band_current.0),
+                        cmp::max(origin.1, self.rubber_band_current.1),
+                    );
+
+                    self.selection = self
+                        .cards
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, card)| {
+                            let (x, y) = card.position;
+                            let (width, height) = card.size;
+                            x < max.0 && x + width > min.0 && y < max.1 && y + height > min.1
+                        })
+                        .map(|(idx, _)| idx)
+                        .collect();
+                }
+
+                self.guides.clear();
+                self.drag_state = DragState::None;
+                true
+            }
+            Msg::SaveBoard => {
+                if let Ok(json) = serde_json::to_string(&self.cards) {
+                    download_json("refboard.json", &json);
+                }
+
+                false
+            }
+            Msg::LoadBoard(json) => {
+                if let Ok(cards) = serde_json::from_str(&json) {
+                    self.cards = cards;
+                    self.selection.clear();
+                    self.drag_state = DragState::None;
+                    cards_changed = true;
+                }
+
+                true
+            }
+            Msg::ImportBoardFiles(files) => {
+                if let Some(file) = files.into_iter().next() {
+                    import_board_file(self.link.clone(), file);
+                }
+
+                false
+            }
+            Msg::Ignore => false,
+            _ => true,
+        };
+
+        if cards_changed {
+            self.persist_to_local_storage();
+        }
+
+        should_render
+    }
+}
+
+impl Renderable<Model> for Model {
+    fn view(&self) -> Html<Self> {
+        let last_mouse_position = self.last_mouse_position;
+
+        html! {
+            <div class="app",>
+                { self.view_toolbar() }
+                <div class="refboard",
+                        style=format!("transform: translate({}px, {}px) scale({});", self.pan.0, self.pan.1, self.zoom),
+                        onmousemove=|e| Msg::Drag(
+                            (e.movement_x(), e.movement_y()),
+                            (e.client_x(), e.client_y()),
+                            DragModifiers { shift: e.shift_key(), alt: e.alt_key() },
+                        ),
+                        onmouseup=|_| Msg::StopDragging,
+                        onmousedown=|e| match e.button() {
+                            MouseButton::Wheel => Msg::StartPanningCanvas,
+                            MouseButton::Left => Msg::StartRubberBand((e.client_x(), e.client_y())),
+                            _ => Msg::Ignore,
+                        },
+                        onwheel=|e| { e.prevent_default(); Msg::Zoom(e.delta_y(), (e.client_x(), e.client_y())) },
+                        ondragover=|e| { e.prevent_default(); Msg::Ignore },
+                        ondrop=|e| {
+                            e.prevent_default();
+                            let position = (e.client_x(), e.client_y());
+                            Msg::ImportFiles(dropped_files(&e), position)
+                        },
+                        onpaste=|e| Msg::ImportFiles(pasted_files(&e), last_mouse_position),>
+                    { for self.cards.iter().map(|c| self.view_card(c)) }
+                    { self.view_rubber_band() }
+                    { self.view_selection() }
+                    { for self.guides.iter().map(|g| view_guide(g)) }
+                </div>
+            </div>
+        }
+    }
+}
+
+impl Model {
+    fn view_card(&self, card: &Card) -> Html<Model> {
+        let card_idx = self.cards.iter().position(|c| c == card);
+
+        match card_idx {
+            Some(idx) => html! {
+                <div class="unselectable card",
+                        style=format!("left: {}px; top: {}px; width: {}px; height: {}px; transform: rotate({}rad); z-index: {};", card.position.0, card.position.1, card.size.0, card.size.1, self.display_rotation(idx), self.display_z(idx)),>
+
+                    // Transformation handles
+
+                    { for CORNERS.iter().map(|&corner| self.view_scale_handle(idx, corner)) }
+
+                    <div class="rotation-handle",
+                        style=format!("right: -{}px; top: -{}px;", HANDLE_RADIUS_PX, HANDLE_RADIUS_PX),
+                        onmousedown=|e| { e.stop_propagation(); Msg::StartDraggingRotateHandle(idx) },
+                        ondragstart=|e| { e.stop_propagation(); Msg::StartDraggingRotateHandle(idx) },
+                        oncontextmenu=|_| Msg::ResetRotation(idx),></div>
+
+                    // Actual image body
+
+                    <div class="image",
+                        onmousedown=|e| { e.stop_propagation(); Msg::StartDraggingCard(idx) },
+                        ondragstart=|e| { e.stop_propagation(); Msg::StartDraggingCard(idx) },
+                        style=format!("width: {}px; height: {}px; background-image: url({});", card.size.0, card.size.1, card.image),></div>
+                </div>
+            },
+            None => html! {},
+        }
+    }
+
+    /// Renders a single scaling handle at `corner` of the card at `idx`.
+    fn view_scale_handle(&self, idx: usize, corner: Corner) -> Html<Model> {
+        let (h, v) = corner.css_position();
+
+        html! {
+            <div class="scaling-handle",
+                style=format!("{}: -{}px; {}: -{}px;", h, HANDLE_RADIUS_PX, v, HANDLE_RADIUS_PX),
+                onmousedown=|e| { e.stop_propagation(); Msg::StartDraggingScaleHandle(idx, corner) },
+                ondragstart=|e| { e.stop_propagation(); Msg::StartDraggingScaleHandle(idx, corner) },></div>
+        }
+    }
+
+    /// Renders the in-progress selection rectangle while a rubber-band drag
+    /// is active.
+    fn view_rubber_band(&self) -> Html<Model> {
+        match self.drag_state {
+            DragState::RubberBand { origin } => {
+                let min = (
+                    cmp::min(origin.0, self.rubber_band_current.0),
+                    cmp::min(origin.1, self.rubber_band_current.1),
+                );
+                let max = (
+                    cmp::max(origin.0, self.rubber_band_current.0),
+                    cmp::max(origin.1, self.rubber_band_current.1),
+                );
+
+                html! {
+                    <div class="rubber-band",
+                            style=format!("left: {}px; top: {}px; width: {}px; height: {}px;", min.0, min.1, max.0 - min.0, max.1 - min.1),></div>
+                }
+            }
+            _ => html! {},
+        }
+    }
+
+    /// Renders the combined outline and group handles around every
+    /// selected card, when more than one card is selected.
+    fn view_selection(&self) -> Html<Model> {
+        if self.selection.len() < 2 {
+            return html! {};
+        }
+
+        match self.selection_bounds() {
+            Some((min, max)) => html! {
+                <div class="selection-outline",
+                        style=format!("left: {}px; top: {}px; width: {}px; height: {}px;", min.0, min.1, max.0 - min.0, max.1 - min.1),>
+
+                    <div class="scaling-handle",
+                        style=format!("right: -{}px; bottom: -{}px;", HANDLE_RADIUS_PX, HANDLE_RADIUS_PX),
+                        onmousedown=|e| { e.stop_propagation(); Msg::StartDraggingGroupScaleHandle },
+                        ondragstart=|e| { e.stop_propagation(); Msg::StartDraggingGroupScaleHandle },></div>
+
+                    <div class="rotation-handle",
+                        style=format!("right: -{}px; top: -{}px;", HANDLE_RADIUS_PX, HANDLE_RADIUS_PX),
+                        onmousedown=|e| { e.stop_propagation(); Msg::StartDraggingGroupRotateHandle((e.client_x(), e.client_y())) },
+                        ondragstart=|e| { e.stop_propagation(); Msg::StartDraggingGroupRotateHandle((e.client_x(), e.client_y())) },></div>
+                </div>
+            },
+            None => html! {},
+        }
+    }
+
+    /// Renders the export/import controls, kept outside `.refboard` so they
+    /// aren't affected by the pan/zoom transform.
+    fn view_toolbar(&self) -> Html<Model> {
+        html! {
+            <div class="toolbar",>
+                <button onclick=|_| Msg::SaveBoard,>{ "Export" }</button>
+                <input type="file",
+                    accept="application/json",
+                    onchange=|e| Msg::ImportBoardFiles(input_files(&e)),/>
+            </div>
+        }
+    }
+}
+
+/// Renders a single alignment guide line, spanning `GUIDE_LINE_REACH_PX`
+/// to either side of the point it passes through.
+fn view_guide(guide: &Guide) -> Html<Model> {
+    match *guide {
+        Guide::Vertical(x) => html! {
+            <div class="guide guide-vertical",
+                    style=format!("left: {}px; top: -{}px; height: {}px;", x, GUIDE_LINE_REACH_PX, GUIDE_LINE_REACH_PX * 2),></div>
+        },
+        Guide::Horizontal(y) => html! {
+            <div class="guide guide-horizontal",
+                    style=format!("top: {}px; left: -{}px; width: {}px;", y, GUIDE_LINE_REACH_PX, GUIDE_LINE_REACH_PX * 2),></div>
+        },
+    }
+}
+
+/// Pulls the `File`s out of a drop event's data transfer, if any.
+fn dropped_files<E: IDragEvent>(event: &E) -> Vec<File> {
+    event
+        .data_transfer()
+        .map(|dt| dt.files().iter().collect())
+        .unwrap_or_default()
+}
+
+/// Pulls the image `File`s out of a paste event's clipboard data, if any.
+/// `stdweb` doesn't expose `ClipboardEvent` directly, so the underlying JS
+/// object is reached into by hand.
+fn pasted_files<E: IEvent>(event: &E) -> Vec<File> {
+    use stdweb::unstable::TryInto;
+    use stdweb::Value;
+
+    let files: Value = js! {
+        var items = (@{event.as_ref()}).clipboardData && (@{event.as_ref()}).clipboardData.items;
+        var files = [];
+        if (items) {
+            for (var i = 0; i < items.length; i++) {
+                if (items[i].kind === "file" && items[i].type.indexOf("image/") === 0) {
+                    files.push(items[i].getAsFile());
+                }
+            }
+        }
+        return files;
+    };
+
+    files.try_into().unwrap_or_default()
+}
+
+/// Pulls the `File`s out of a file `<input>`'s change event, if any.
+fn input_files<E: IEvent>(event: &E) -> Vec<File> {
+    use stdweb::unstable::TryInto;
+    use stdweb::Value;
+
+    let files: Value = js! {
+        var input = (@{event.as_ref()}).target;
+        var files = [];
+        if (input && input.files) {
+            for (var i = 0; i < input.files.length; i++) {
+                files.push(input.files[i]);
+            }
+        }
+        return files;
+    };
+
+    files.try_into().unwrap_or_default()
+}
+
+/// The current time in milliseconds, per `Date.now()`. `stdweb` doesn't
+/// expose this directly, so it's reached for by hand.
+fn now_ms() -> f64 {
+    use stdweb::unstable::TryInto;
+    use stdweb::Value;
+
+    let now: Value = js! {
+        return Date.now();
+    };
+
+    now.try_into().unwrap_or(0.0)
+}
+
+/// Triggers a browser download of `json` saved as `filename`.
+fn download_json(filename: &str, json: &str) {
+    js! {
+        var blob = new Blob([@{json}], { type: "application/json" });
+        var url = URL.createObjectURL(blob);
+        var a = document.createElement("a");
+        a.href = url;
+        a.download = @{filename};
+        document.body.appendChild(a);
+        a.click();
+        document.body.removeChild(a);
+        URL.revokeObjectURL(url);
+    }
+}
+
+/// Reads `file` as text and dispatches `Msg::LoadBoard` once the read
+/// completes.
+fn import_board_file(link: ComponentLink<Model>, file: File) {
+    let reader = FileReader::new();
+
+    let reader_clone = reader.clone();
+    reader.add_event_listener(move |_: LoadEndEvent| {
+        if let Some(FileReaderResult::String(text)) = reader_clone.result() {
+            link.send_self(Msg::LoadBoard(text));
+        }
+    });
+
+    reader.read_as_text(&file).unwrap();
+}
+
+/// Reads `file` as a base64 data URL and dispatches `Msg::CreateCard` once
+/// the read completes.
+fn import_file(link: ComponentLink<Model>, file: File, position: (i32, i32)) {
+    let reader = FileReader::new();
+
+    let reader_clone = reader.clone();
+    reader.add_event_listener(move |_: LoadEndEvent| {
+        if let Some(FileReaderResult::String(data_url)) = reader_clone.result() {
+            link.send_self(Msg::CreateCard(data_url, position));
+        }
+    });
+
+    reader.read_as_data_url(&file).unwrap();
+}
+
+/// Loads `src` in a detached `<img>` element so the card at `idx` can be
+/// resized to the image's natural dimensions once they're known.
+fn probe_image_size(link: ComponentLink<Model>, idx: usize, src: String) {
+    let image = ImageElement::new();
+    image.set_src(&src);
+
+    let image_clone = image.clone();
+    image.add_event_listener(move |_: ResourceLoadEvent| {
+        let size = (image_clone.width() as i32, image_clone.height() as i32);
+        link.send_self(Msg::SetCardSize(idx, size));
+    });
+}